@@ -0,0 +1,283 @@
+//! A serde-lite layer for converting between [`Json`] and user types.
+//!
+//! There is no derive macro here — implement [`Decodable`]/[`Encodable`] by
+//! hand for your struct, field by field, using [`Decodable::decode_field`]
+//! so that `Option<T>` fields are treated as optional keys rather than
+//! required ones:
+//!
+//! ```ignore
+//! impl Decodable for Config {
+//!     fn decode(value: &Json) -> Result<Self, DecodeError> {
+//!         Ok(Config {
+//!             name: String::decode_field(value, "name")?,
+//!             age: Option::<f64>::decode_field(value, "age")?,
+//!         })
+//!     }
+//! }
+//!
+//! let cfg: Config = config_json.decode()?;
+//! ```
+
+use crate::Json;
+use std::collections::HashMap;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    #[error("expected {0}, found {1}")]
+    ExpectedError(&'static str, &'static str),
+    #[error("missing key `{0}`")]
+    MissingKey(String),
+}
+
+pub trait Decodable: Sized {
+    fn decode(value: &Json) -> Result<Self, DecodeError>;
+
+    /// Looks `key` up on `object` and decodes it. Overridden by `Option<T>`
+    /// so that a missing key decodes to `None` instead of an error.
+    fn decode_field(object: &Json, key: &str) -> Result<Self, DecodeError> {
+        let value = object
+            .get(key)
+            .ok_or_else(|| DecodeError::MissingKey(key.to_string()))?;
+        Self::decode(value)
+    }
+}
+
+pub trait Encodable {
+    fn encode(&self) -> Json;
+}
+
+impl Json {
+    pub fn decode<T: Decodable>(&self) -> Result<T, DecodeError> {
+        T::decode(self)
+    }
+}
+
+fn type_name(value: &Json) -> &'static str {
+    match value {
+        Json::String(_) => "String",
+        Json::Number(_) => "Number",
+        Json::Boolean(_) => "Boolean",
+        Json::Array(_) => "Array",
+        Json::Object(_) => "Object",
+        Json::Null => "Null",
+    }
+}
+
+impl Decodable for String {
+    fn decode(value: &Json) -> Result<Self, DecodeError> {
+        match value {
+            Json::String(value) => Ok(value.clone()),
+            other => Err(DecodeError::ExpectedError("String", type_name(other))),
+        }
+    }
+}
+
+impl Decodable for f64 {
+    fn decode(value: &Json) -> Result<Self, DecodeError> {
+        match value {
+            Json::Number(value) => Ok(*value),
+            other => Err(DecodeError::ExpectedError("Number", type_name(other))),
+        }
+    }
+}
+
+impl Decodable for bool {
+    fn decode(value: &Json) -> Result<Self, DecodeError> {
+        match value {
+            Json::Boolean(value) => Ok(*value),
+            other => Err(DecodeError::ExpectedError("Boolean", type_name(other))),
+        }
+    }
+}
+
+impl<T: Decodable> Decodable for Option<T> {
+    fn decode(value: &Json) -> Result<Self, DecodeError> {
+        match value {
+            Json::Null => Ok(None),
+            value => Ok(Some(T::decode(value)?)),
+        }
+    }
+
+    fn decode_field(object: &Json, key: &str) -> Result<Self, DecodeError> {
+        match object.get(key) {
+            Some(value) => Self::decode(value),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<T: Decodable> Decodable for Vec<T> {
+    fn decode(value: &Json) -> Result<Self, DecodeError> {
+        match value {
+            Json::Array(items) => items.iter().map(T::decode).collect(),
+            other => Err(DecodeError::ExpectedError("Array", type_name(other))),
+        }
+    }
+}
+
+impl<T: Decodable> Decodable for HashMap<String, T> {
+    fn decode(value: &Json) -> Result<Self, DecodeError> {
+        match value {
+            Json::Object(map) => map
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), T::decode(value)?)))
+                .collect(),
+            other => Err(DecodeError::ExpectedError("Object", type_name(other))),
+        }
+    }
+}
+
+impl Encodable for String {
+    fn encode(&self) -> Json {
+        Json::String(self.clone())
+    }
+}
+
+impl Encodable for f64 {
+    fn encode(&self) -> Json {
+        Json::Number(*self)
+    }
+}
+
+impl Encodable for bool {
+    fn encode(&self) -> Json {
+        Json::Boolean(*self)
+    }
+}
+
+impl<T: Encodable> Encodable for Option<T> {
+    fn encode(&self) -> Json {
+        match self {
+            Some(value) => value.encode(),
+            None => Json::Null,
+        }
+    }
+}
+
+impl<T: Encodable> Encodable for Vec<T> {
+    fn encode(&self) -> Json {
+        Json::Array(self.iter().map(Encodable::encode).collect())
+    }
+}
+
+impl<T: Encodable> Encodable for HashMap<String, T> {
+    fn encode(&self) -> Json {
+        Json::Object(
+            self.iter()
+                .map(|(key, value)| (key.clone(), value.encode()))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string() {
+        let json = Json::String("hello".to_string());
+        assert_eq!(String::decode(&json).unwrap(), "hello");
+        assert_eq!("hello".to_string().encode(), json);
+
+        let err = String::decode(&Json::Number(1.0)).unwrap_err();
+        assert!(matches!(
+            err,
+            DecodeError::ExpectedError("String", "Number")
+        ));
+    }
+
+    #[test]
+    fn test_f64() {
+        let json = Json::Number(26.5);
+        assert_eq!(f64::decode(&json).unwrap(), 26.5);
+        assert_eq!(26.5.encode(), json);
+
+        let err = f64::decode(&Json::Null).unwrap_err();
+        assert!(matches!(err, DecodeError::ExpectedError("Number", "Null")));
+    }
+
+    #[test]
+    fn test_bool() {
+        let json = Json::Boolean(true);
+        assert!(bool::decode(&json).unwrap());
+        assert_eq!(true.encode(), json);
+
+        let err = bool::decode(&Json::Number(1.0)).unwrap_err();
+        assert!(matches!(
+            err,
+            DecodeError::ExpectedError("Boolean", "Number")
+        ));
+    }
+
+    #[test]
+    fn test_vec() {
+        let json = Json::Array(vec![Json::Number(1.0), Json::Number(2.0)]);
+        assert_eq!(Vec::<f64>::decode(&json).unwrap(), vec![1.0, 2.0]);
+        assert_eq!(vec![1.0, 2.0].encode(), json);
+
+        let err = Vec::<f64>::decode(&Json::Null).unwrap_err();
+        assert!(matches!(err, DecodeError::ExpectedError("Array", "Null")));
+    }
+
+    #[test]
+    fn test_hash_map() {
+        let json = Json::Object(vec![("a".to_string(), Json::Number(1.0))]);
+        let mut expected = HashMap::new();
+        expected.insert("a".to_string(), 1.0);
+        assert_eq!(HashMap::<String, f64>::decode(&json).unwrap(), expected);
+        assert_eq!(expected.encode(), json);
+
+        let err = HashMap::<String, f64>::decode(&Json::Null).unwrap_err();
+        assert!(matches!(err, DecodeError::ExpectedError("Object", "Null")));
+    }
+
+    #[test]
+    fn test_option_decode() {
+        assert_eq!(Option::<f64>::decode(&Json::Null).unwrap(), None);
+        assert_eq!(
+            Option::<f64>::decode(&Json::Number(1.0)).unwrap(),
+            Some(1.0)
+        );
+
+        let err = Option::<f64>::decode(&Json::String("x".to_string())).unwrap_err();
+        assert!(matches!(
+            err,
+            DecodeError::ExpectedError("Number", "String")
+        ));
+    }
+
+    #[test]
+    fn test_option_decode_field_missing_key_vs_null_value() {
+        let object = Json::Object(vec![("age".to_string(), Json::Null)]);
+        assert_eq!(Option::<f64>::decode_field(&object, "age").unwrap(), None);
+        assert_eq!(Option::<f64>::decode_field(&object, "name").unwrap(), None);
+
+        let object = Json::Object(vec![("age".to_string(), Json::Number(30.0))]);
+        assert_eq!(
+            Option::<f64>::decode_field(&object, "age").unwrap(),
+            Some(30.0)
+        );
+    }
+
+    #[test]
+    fn test_decode_field_missing_required_key() {
+        let object = Json::Object(vec![]);
+        let err = String::decode_field(&object, "name").unwrap_err();
+        assert!(matches!(err, DecodeError::MissingKey(key) if key == "name"));
+    }
+
+    #[test]
+    fn test_option_encode() {
+        assert_eq!(Some(1.0).encode(), Json::Number(1.0));
+        assert_eq!(None::<f64>.encode(), Json::Null);
+    }
+
+    #[test]
+    fn test_json_decode_method() {
+        let json = Json::String("hi".to_string());
+        let decoded: String = json.decode().unwrap();
+        assert_eq!(decoded, "hi");
+    }
+}
@@ -0,0 +1,313 @@
+//! Streaming, event-based pull parser.
+//!
+//! Unlike [`crate::Json::parse`], which builds the whole document tree in
+//! memory, [`Parser`] walks the input one token at a time and yields a
+//! [`JsonEvent`] per call to `next`. This lets callers process JSON
+//! documents too large to materialize, or bail out early once they've seen
+//! what they need.
+
+use crate::{boolean, null, number, string, Json};
+use nom::{character::complete::char, character::complete::multispace0, IResult};
+
+/// One step of a streaming JSON document.
+#[derive(Debug, PartialEq, Clone)]
+pub enum JsonEvent {
+    ObjectStart,
+    ObjectEnd,
+    ArrayStart,
+    ArrayEnd,
+    StringValue(String),
+    NumberValue(f64),
+    BooleanValue(bool),
+    NullValue,
+    /// The input stopped matching the JSON grammar at this byte offset.
+    Error(usize),
+}
+
+/// An element of the path from the document root to the value currently
+/// being parsed, as returned by [`Parser::stack`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum StackElement {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone)]
+enum Frame {
+    Array { first: bool },
+    Object { first: bool },
+}
+
+/// A pull parser that yields [`JsonEvent`]s as it walks an input string.
+pub struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    frames: Vec<Frame>,
+    stack: Vec<StackElement>,
+    started: bool,
+    done: bool,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Parser {
+            input,
+            pos: 0,
+            frames: Vec::new(),
+            stack: Vec::new(),
+            started: false,
+            done: false,
+        }
+    }
+
+    /// The path of keys/indices from the root to the value currently being
+    /// parsed, without materializing the rest of the document.
+    pub fn stack(&self) -> &[StackElement] {
+        &self.stack
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn advance_to(&mut self, remaining: &'a str) {
+        self.pos = self.input.len() - remaining.len();
+    }
+
+    fn skip_whitespace(&mut self) {
+        let (rest, _) = whitespace(self.rest()).unwrap();
+        self.advance_to(rest);
+    }
+
+    fn token(&mut self, c: char) -> bool {
+        match token(self.rest(), c) {
+            Ok((rest, _)) => {
+                self.advance_to(rest);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn fail(&mut self) -> JsonEvent {
+        self.done = true;
+        JsonEvent::Error(self.pos)
+    }
+
+    fn end_frame(&mut self, event: JsonEvent) -> JsonEvent {
+        self.frames.pop();
+        self.stack.pop();
+        event
+    }
+
+    /// Parses one value: either the start of a nested array/object (in
+    /// which case a frame is pushed and only the `*Start` event is
+    /// returned), or a complete scalar value.
+    fn parse_value(&mut self) -> JsonEvent {
+        self.skip_whitespace();
+        if self.token('[') {
+            self.frames.push(Frame::Array { first: true });
+            self.stack.push(StackElement::Index(0));
+            return JsonEvent::ArrayStart;
+        }
+        if self.token('{') {
+            self.frames.push(Frame::Object { first: true });
+            self.stack.push(StackElement::Key(String::new()));
+            return JsonEvent::ObjectStart;
+        }
+        match string(self.rest())
+            .or_else(|_| number(self.rest()))
+            .or_else(|_| boolean(self.rest()))
+            .or_else(|_| null(self.rest()))
+        {
+            Ok((rest, json)) => {
+                self.advance_to(rest);
+                match json {
+                    Json::String(value) => JsonEvent::StringValue(value),
+                    Json::Number(value) => JsonEvent::NumberValue(value),
+                    Json::Boolean(value) => JsonEvent::BooleanValue(value),
+                    Json::Null => JsonEvent::NullValue,
+                    Json::Array(_) | Json::Object(_) => unreachable!(),
+                }
+            }
+            Err(_) => self.fail(),
+        }
+    }
+
+    fn next_array_event(&mut self) -> JsonEvent {
+        self.skip_whitespace();
+        if self.token(']') {
+            return self.end_frame(JsonEvent::ArrayEnd);
+        }
+        let first = matches!(self.frames.last(), Some(Frame::Array { first: true }));
+        if !first {
+            if !self.token(',') {
+                return self.fail();
+            }
+            if let Some(StackElement::Index(index)) = self.stack.last_mut() {
+                *index += 1;
+            }
+        }
+        if let Some(Frame::Array { first }) = self.frames.last_mut() {
+            *first = false;
+        }
+        self.parse_value()
+    }
+
+    fn next_object_event(&mut self) -> JsonEvent {
+        self.skip_whitespace();
+        if self.token('}') {
+            return self.end_frame(JsonEvent::ObjectEnd);
+        }
+        let first = matches!(self.frames.last(), Some(Frame::Object { first: true }));
+        if !first && !self.token(',') {
+            return self.fail();
+        }
+        self.skip_whitespace();
+        let key = match string(self.rest()) {
+            Ok((rest, Json::String(key))) => {
+                self.advance_to(rest);
+                key
+            }
+            _ => return self.fail(),
+        };
+        self.skip_whitespace();
+        if !self.token(':') {
+            return self.fail();
+        }
+        if let Some(StackElement::Key(current)) = self.stack.last_mut() {
+            *current = key;
+        }
+        if let Some(Frame::Object { first }) = self.frames.last_mut() {
+            *first = false;
+        }
+        self.parse_value()
+    }
+}
+
+impl<'a> Iterator for Parser<'a> {
+    type Item = JsonEvent;
+
+    fn next(&mut self) -> Option<JsonEvent> {
+        if self.done {
+            return None;
+        }
+
+        // The top-level value/structure already finished on a previous call.
+        // Like `Json::parse`, trailing non-whitespace input is an error.
+        if self.started && self.frames.is_empty() {
+            self.done = true;
+            self.skip_whitespace();
+            return if self.rest().is_empty() {
+                None
+            } else {
+                Some(JsonEvent::Error(self.pos))
+            };
+        }
+        self.started = true;
+
+        let event = match self.frames.last() {
+            None => self.parse_value(),
+            Some(Frame::Array { .. }) => self.next_array_event(),
+            Some(Frame::Object { .. }) => self.next_object_event(),
+        };
+
+        if matches!(event, JsonEvent::Error(_)) {
+            self.done = true;
+        }
+
+        Some(event)
+    }
+}
+
+fn token(input: &str, c: char) -> IResult<&str, char> {
+    char(c)(input)
+}
+
+fn whitespace(input: &str) -> IResult<&str, &str> {
+    multispace0(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flat_array() {
+        let events: Vec<_> = Parser::new("[1, 2, 3]").collect();
+        assert_eq!(
+            events,
+            vec![
+                JsonEvent::ArrayStart,
+                JsonEvent::NumberValue(1.0),
+                JsonEvent::NumberValue(2.0),
+                JsonEvent::NumberValue(3.0),
+                JsonEvent::ArrayEnd,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_object_in_array() {
+        let mut parser = Parser::new(r#"[{"a": 1}]"#);
+
+        assert_eq!(parser.next(), Some(JsonEvent::ArrayStart));
+        assert_eq!(parser.stack(), [StackElement::Index(0)]);
+
+        assert_eq!(parser.next(), Some(JsonEvent::ObjectStart));
+        assert_eq!(
+            parser.stack(),
+            [StackElement::Index(0), StackElement::Key(String::new())]
+        );
+
+        assert_eq!(parser.next(), Some(JsonEvent::NumberValue(1.0)));
+        assert_eq!(
+            parser.stack(),
+            [StackElement::Index(0), StackElement::Key("a".to_string())]
+        );
+
+        assert_eq!(parser.next(), Some(JsonEvent::ObjectEnd));
+        assert_eq!(parser.stack(), [StackElement::Index(0)]);
+
+        assert_eq!(parser.next(), Some(JsonEvent::ArrayEnd));
+        assert_eq!(parser.stack(), []);
+
+        assert_eq!(parser.next(), None);
+    }
+
+    #[test]
+    fn test_empty_array_and_object() {
+        let events: Vec<_> = Parser::new("[]").collect();
+        assert_eq!(events, vec![JsonEvent::ArrayStart, JsonEvent::ArrayEnd]);
+
+        let events: Vec<_> = Parser::new("{}").collect();
+        assert_eq!(events, vec![JsonEvent::ObjectStart, JsonEvent::ObjectEnd]);
+    }
+
+    #[test]
+    fn test_malformed_input_emits_error() {
+        let events: Vec<_> = Parser::new("[1, ]").collect();
+        assert_eq!(events.last(), Some(&JsonEvent::Error(4)));
+
+        let events: Vec<_> = Parser::new("{\"a\" 1}").collect();
+        assert_eq!(events.last(), Some(&JsonEvent::Error(5)));
+
+        let events: Vec<_> = Parser::new("nope").collect();
+        assert_eq!(events, vec![JsonEvent::Error(0)]);
+    }
+
+    #[test]
+    fn test_trailing_garbage_after_top_level_value_is_an_error() {
+        let events: Vec<_> = Parser::new("42 garbage").collect();
+        assert_eq!(
+            events,
+            vec![JsonEvent::NumberValue(42.0), JsonEvent::Error(3)]
+        );
+
+        let events: Vec<_> = Parser::new("[1, 2] trailing").collect();
+        assert_eq!(events.last(), Some(&JsonEvent::Error(7)));
+
+        let events: Vec<_> = Parser::new("  42  ").collect();
+        assert_eq!(events, vec![JsonEvent::NumberValue(42.0)]);
+    }
+}
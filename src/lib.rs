@@ -1,17 +1,22 @@
 use anyhow;
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{alpha1, alphanumeric0, alphanumeric1, char, digit1, multispace0},
-    combinator::{eof, map, opt, recognize},
+    bytes::complete::{tag, take_while_m_n},
+    character::complete::{char, digit1, multispace0, satisfy},
+    combinator::{eof, map, opt, recognize, value as val},
     error::{Error, ErrorKind},
-    multi::{many0, separated_list0},
-    sequence::{delimited, tuple},
+    multi::{fold_many0, separated_list0},
+    sequence::{delimited, preceded, tuple},
     Finish, IResult,
 };
-use std::{collections::HashMap, fmt};
+use std::fmt;
 use thiserror::Error;
 
+mod decode;
+mod event;
+pub use decode::{Decodable, DecodeError, Encodable};
+pub use event::{JsonEvent, Parser, StackElement};
+
 #[derive(Debug, Error)]
 #[error("parse error: {{ input: `{}`, code: `{}` }}", input, code.description())]
 pub struct JsonParseError {
@@ -25,13 +30,13 @@ pub enum Json {
     Number(f64),
     Boolean(bool),
     Array(Vec<Json>),
-    Object(HashMap<String, Json>),
+    Object(Vec<(String, Json)>),
     Null,
 }
 
 impl Json {
     pub fn parse(input: &str) -> anyhow::Result<Json> {
-        match tuple((alt((array, object)), eof))(input).finish() {
+        match tuple((value, eof))(input).finish() {
             Ok((_, (json, _))) => Ok(json),
             Err(Error { input, code }) => Err(JsonParseError {
                 input: input.to_string(),
@@ -40,26 +45,138 @@ impl Json {
             .into()),
         }
     }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    /// Renders the document with `indent` spaces per nesting level, one
+    /// array/object entry per line. Scalars stay inline; compact [`Display`]
+    /// output remains the default via `to_string`/`format!`.
+    pub fn to_pretty_string(&self, indent: usize) -> String {
+        let mut buf = String::new();
+        self.write_pretty(&mut buf, indent, 0);
+        buf
+    }
+
+    fn write_pretty(&self, buf: &mut String, indent: usize, depth: usize) {
+        match self {
+            Json::Array(items) if items.is_empty() => buf.push_str("[]"),
+            Json::Array(items) => {
+                buf.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    buf.push_str(if i == 0 { "\n" } else { ",\n" });
+                    buf.push_str(&" ".repeat(indent * (depth + 1)));
+                    item.write_pretty(buf, indent, depth + 1);
+                }
+                buf.push('\n');
+                buf.push_str(&" ".repeat(indent * depth));
+                buf.push(']');
+            }
+            Json::Object(entries) if entries.is_empty() => buf.push_str("{}"),
+            Json::Object(entries) => {
+                buf.push('{');
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    buf.push_str(if i == 0 { "\n" } else { ",\n" });
+                    buf.push_str(&" ".repeat(indent * (depth + 1)));
+                    buf.push_str(&format!("\"{}\": ", escape_string(key)));
+                    value.write_pretty(buf, indent, depth + 1);
+                }
+                buf.push('\n');
+                buf.push_str(&" ".repeat(indent * depth));
+                buf.push('}');
+            }
+            scalar => buf.push_str(&scalar.to_string()),
+        }
+    }
+}
+
+fn value(input: &str) -> IResult<&str, Json> {
+    delimited(
+        multispace0,
+        alt((string, number, boolean, array, object, null)),
+        multispace0,
+    )(input)
 }
 
 fn string(input: &str) -> IResult<&str, Json> {
-    let (input, value) = delimited(char('"'), alphanumeric0, char('"'))(input)?;
-    Ok((input, Json::String(value.to_string())))
+    let (input, value) = delimited(
+        char('"'),
+        fold_many0(string_char, String::new, |mut acc, c| {
+            acc.push(c);
+            acc
+        }),
+        char('"'),
+    )(input)?;
+    Ok((input, Json::String(value)))
+}
+
+fn string_char(input: &str) -> IResult<&str, char> {
+    alt((
+        escaped_char,
+        satisfy(|c| c != '"' && c != '\\' && c >= '\u{20}'),
+    ))(input)
+}
+
+fn escaped_char(input: &str) -> IResult<&str, char> {
+    preceded(
+        char('\\'),
+        alt((
+            val('"', char('"')),
+            val('\\', char('\\')),
+            val('/', char('/')),
+            val('\u{8}', char('b')),
+            val('\u{c}', char('f')),
+            val('\n', char('n')),
+            val('\r', char('r')),
+            val('\t', char('t')),
+            unicode_char,
+        )),
+    )(input)
+}
+
+fn unicode_escape(input: &str) -> IResult<&str, u16> {
+    preceded(char('u'), hex_code_unit)(input)
+}
+
+fn unicode_char(input: &str) -> IResult<&str, char> {
+    let (input, high) = unicode_escape(input)?;
+    if (0xD800..0xDC00).contains(&high) {
+        let (input, low) = preceded(tag("\\u"), hex_code_unit)(input)?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)));
+        }
+        let code_point = ((high as u32 - 0xD800) << 10) + (low as u32 - 0xDC00) + 0x10000;
+        let c = char::from_u32(code_point)
+            .ok_or_else(|| nom::Err::Error(Error::new(input, ErrorKind::Verify)))?;
+        Ok((input, c))
+    } else if (0xDC00..0xE000).contains(&high) {
+        Err(nom::Err::Error(Error::new(input, ErrorKind::Verify)))
+    } else {
+        let c = char::from_u32(high as u32)
+            .ok_or_else(|| nom::Err::Error(Error::new(input, ErrorKind::Char)))?;
+        Ok((input, c))
+    }
+}
+
+fn hex_code_unit(input: &str) -> IResult<&str, u16> {
+    let (input, hex) = take_while_m_n(4, 4, |c: char| c.is_ascii_hexdigit())(input)?;
+    let code_unit = u16::from_str_radix(hex, 16)
+        .map_err(|_| nom::Err::Error(Error::new(input, ErrorKind::HexDigit)))?;
+    Ok((input, code_unit))
 }
 
 fn number(input: &str) -> IResult<&str, Json> {
-    let (input, (unary_minus, value)) = tuple((
+    let (input, value) = recognize(tuple((
         opt(char('-')),
-        alt((recognize(tuple((digit1, char('.'), digit1))), digit1)),
-    ))(input)?;
-    Ok((
-        input,
-        Json::Number(if unary_minus.is_some() {
-            -value.parse::<f64>().unwrap()
-        } else {
-            value.parse::<f64>().unwrap()
-        }),
-    ))
+        digit1,
+        opt(tuple((char('.'), digit1))),
+        opt(tuple((alt((char('e'), char('E'))), opt(alt((char('+'), char('-')))), digit1))),
+    )))(input)?;
+    Ok((input, Json::Number(value.parse::<f64>().unwrap())))
 }
 
 fn boolean(input: &str) -> IResult<&str, Json> {
@@ -70,10 +187,7 @@ fn boolean(input: &str) -> IResult<&str, Json> {
 fn array(input: &str) -> IResult<&str, Json> {
     let (input, json_list) = delimited(
         ws_char('['),
-        separated_list0(
-            ws_char(','),
-            alt((string, number, boolean, array, object, null)),
-        ),
+        separated_list0(ws_char(','), value),
         ws_char(']'),
     )(input)?;
     Ok((input, Json::Array(json_list)))
@@ -85,21 +199,20 @@ fn object(input: &str) -> IResult<&str, Json> {
         separated_list0(
             ws_char(','),
             map(
-                tuple((
-                    delimited(
-                        char('"'),
-                        recognize(tuple((alpha1, many0(alphanumeric1)))),
-                        char('"'),
-                    ),
-                    ws_char(':'),
-                    alt((string, number, boolean, array, object, null)),
-                )),
-                |(key, _, value)| (key.to_string(), value),
+                tuple((object_key, ws_char(':'), value)),
+                |(key, _, value)| (key, value),
             ),
         ),
         ws_char('}'),
     )(input)?;
-    Ok((input, Json::Object(key_value_list.into_iter().collect())))
+    Ok((input, Json::Object(key_value_list)))
+}
+
+fn object_key(input: &str) -> IResult<&str, String> {
+    map(string, |json| match json {
+        Json::String(key) => key,
+        _ => unreachable!(),
+    })(input)
 }
 
 fn null(input: &str) -> IResult<&str, Json> {
@@ -114,7 +227,7 @@ fn ws_char<'a>(c: char) -> impl FnMut(&'a str) -> IResult<&'a str, char> {
 impl fmt::Display for Json {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Json::String(value) => write!(f, "\"{}\"", value),
+            Json::String(value) => write!(f, "\"{}\"", escape_string(value)),
             Json::Number(value) => write!(f, "{}", value),
             Json::Boolean(value) => write!(f, "{}", value),
             Json::Array(json_list) => write!(
@@ -131,7 +244,7 @@ impl fmt::Display for Json {
                 "{{{}}}",
                 json_map
                     .iter()
-                    .map(|(key, value)| format!("\"{}\": {}", key, value))
+                    .map(|(key, value)| format!("\"{}\": {}", escape_string(key), value))
                     .collect::<Vec<String>>()
                     .join(", ")
             ),
@@ -140,10 +253,27 @@ impl fmt::Display for Json {
     }
 }
 
+fn escape_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c < '\u{20}' => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 #[cfg(test)]
 mod tests {
     use crate::Json;
-    use std::collections::HashMap;
 
     #[test]
     fn test_parse() {
@@ -151,14 +281,10 @@ mod tests {
 
         assert_eq!(
             json,
-            Json::Object(
-                vec![
-                    ("name".to_string(), Json::String("Tanaka".to_string())),
-                    ("age".to_string(), Json::Number(26.0))
-                ]
-                .into_iter()
-                .collect::<HashMap<String, Json>>()
-            )
+            Json::Object(vec![
+                ("name".to_string(), Json::String("Tanaka".to_string())),
+                ("age".to_string(), Json::Number(26.0)),
+            ])
         );
 
         let json = Json::parse(r#"[true, false, null]"#).unwrap();
@@ -175,77 +301,128 @@ mod tests {
 
         assert_eq!(
             json,
-            Json::Object(
-                vec![(
-                    "persons".to_string(),
-                    Json::Array(vec![
-                        Json::Object(
-                            vec![
-                                ("name".to_string(), Json::String("Tanaka".to_string())),
-                                ("age".to_string(), Json::Number(26.0)),
-                            ]
-                            .into_iter()
-                            .collect::<HashMap<String, Json>>(),
-                        ),
-                        Json::Object(
-                            vec![
-                                ("name".to_string(), Json::String("Yamada".to_string())),
-                                ("age".to_string(), Json::Number(28.0)),
-                            ]
-                            .into_iter()
-                            .collect::<HashMap<String, Json>>(),
-                        ),
-                    ])
-                ),]
-                .into_iter()
-                .collect::<HashMap<String, Json>>()
-            )
+            Json::Object(vec![(
+                "persons".to_string(),
+                Json::Array(vec![
+                    Json::Object(vec![
+                        ("name".to_string(), Json::String("Tanaka".to_string())),
+                        ("age".to_string(), Json::Number(26.0)),
+                    ]),
+                    Json::Object(vec![
+                        ("name".to_string(), Json::String("Yamada".to_string())),
+                        ("age".to_string(), Json::Number(28.0)),
+                    ]),
+                ])
+            )])
+        );
+
+        let json = Json::parse(r#"{"0": 1, "user-id": 2}"#).unwrap();
+
+        assert_eq!(
+            json,
+            Json::Object(vec![
+                ("0".to_string(), Json::Number(1.0)),
+                ("user-id".to_string(), Json::Number(2.0)),
+            ])
         );
     }
 
     #[test]
-    fn test_display() {
-        let json = Json::Object(
-            vec![
-                ("name".to_string(), Json::String("Tanaka".to_string())),
-                ("age".to_string(), Json::Number(26.0)),
-            ]
-            .into_iter()
-            .collect::<HashMap<String, Json>>(),
+    fn test_string_escapes() {
+        let json = Json::parse(r#""hello world""#).unwrap();
+
+        assert_eq!(json, Json::String("hello world".to_string()));
+
+        let json = Json::parse(r#""line\nbreak\ttab\"quote\\backslash""#).unwrap();
+
+        assert_eq!(
+            json,
+            Json::String("line\nbreak\ttab\"quote\\backslash".to_string())
         );
 
+        let json = Json::parse(r#""a\/b""#).unwrap();
+
+        assert_eq!(json, Json::String("a/b".to_string()));
+
+        let json = Json::parse(r#""\u0041""#).unwrap();
+
+        assert_eq!(json, Json::String("A".to_string()));
+
+        let json = Json::parse(r#""\uD83D\uDE00""#).unwrap();
+
+        assert_eq!(json, Json::String("😀".to_string()));
+
+        assert!(Json::parse(r#""\uD83D""#).is_err());
+        assert!(Json::parse(r#""\uDE00""#).is_err());
+        assert!(Json::parse("\"\u{0}\"").is_err());
+    }
+
+    #[test]
+    fn test_number_grammar() {
+        assert_eq!(Json::parse("0").unwrap(), Json::Number(0.0));
+        assert_eq!(Json::parse("26").unwrap(), Json::Number(26.0));
+        assert_eq!(Json::parse("-26").unwrap(), Json::Number(-26.0));
+        assert_eq!(Json::parse("26.5").unwrap(), Json::Number(26.5));
+        assert_eq!(Json::parse("-0.5").unwrap(), Json::Number(-0.5));
+        assert_eq!(Json::parse("1e10").unwrap(), Json::Number(1e10));
+        assert_eq!(Json::parse("1E10").unwrap(), Json::Number(1e10));
+        assert_eq!(Json::parse("-0.5E-3").unwrap(), Json::Number(-0.5e-3));
+        assert_eq!(Json::parse("6.022e23").unwrap(), Json::Number(6.022e23));
+        assert_eq!(Json::parse("2e+3").unwrap(), Json::Number(2e3));
+
+        assert!(Json::parse(".5").is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        let json = Json::Object(vec![
+            ("name".to_string(), Json::String("Tanaka".to_string())),
+            ("age".to_string(), Json::Number(26.0)),
+        ]);
+
         assert_eq!(Json::parse(format!("{}", json).as_str()).unwrap(), json);
+        assert_eq!(format!("{}", json), r#"{"name": "Tanaka", "age": 26}"#);
 
         let json = Json::Array(vec![Json::Boolean(true), Json::Boolean(false), Json::Null]);
 
         assert_eq!(Json::parse(format!("{}", json).as_str()).unwrap(), json);
 
-        let json = Json::Object(
-            vec![(
-                "persons".to_string(),
-                Json::Array(vec![
-                    Json::Object(
-                        vec![
-                            ("name".to_string(), Json::String("Tanaka".to_string())),
-                            ("age".to_string(), Json::Number(26.0)),
-                        ]
-                        .into_iter()
-                        .collect::<HashMap<String, Json>>(),
-                    ),
-                    Json::Object(
-                        vec![
-                            ("name".to_string(), Json::String("Yamada".to_string())),
-                            ("age".to_string(), Json::Number(28.0)),
-                        ]
-                        .into_iter()
-                        .collect::<HashMap<String, Json>>(),
-                    ),
+        let json = Json::Object(vec![(
+            "persons".to_string(),
+            Json::Array(vec![
+                Json::Object(vec![
+                    ("name".to_string(), Json::String("Tanaka".to_string())),
+                    ("age".to_string(), Json::Number(26.0)),
                 ]),
-            )]
-            .into_iter()
-            .collect::<HashMap<String, Json>>(),
-        );
+                Json::Object(vec![
+                    ("name".to_string(), Json::String("Yamada".to_string())),
+                    ("age".to_string(), Json::Number(28.0)),
+                ]),
+            ]),
+        )]);
 
         assert_eq!(Json::parse(format!("{}", json).as_str()).unwrap(), json);
+
+        let json = Json::Object(vec![(
+            "a\"b".to_string(),
+            Json::String("line\nbreak \\ quote \"".to_string()),
+        )]);
+
+        assert_eq!(Json::parse(format!("{}", json).as_str()).unwrap(), json);
+    }
+
+    #[test]
+    fn test_to_pretty_string() {
+        let json = Json::Object(vec![
+            ("name".to_string(), Json::String("Tanaka".to_string())),
+            ("pets".to_string(), Json::Array(vec![])),
+        ]);
+
+        assert_eq!(
+            json.to_pretty_string(2),
+            "{\n  \"name\": \"Tanaka\",\n  \"pets\": []\n}"
+        );
+
+        assert_eq!(Json::parse(json.to_pretty_string(2).as_str()).unwrap(), json);
     }
 }